@@ -0,0 +1,28 @@
+use std::collections::VecDeque;
+use std::io;
+
+use bytes::Bytes;
+
+/// Encodes a frame into a queue of owned [`Bytes`] chunks, rather than into a
+/// single contiguous buffer.
+///
+/// Implement this in addition to [`Encoder`] to let a [`FramedWrite`] built
+/// with [`FramedWrite::new_vectored`] write a frame with
+/// [`AsyncWrite::poll_write_vectored`] instead of first copying it into a
+/// contiguous write buffer. This is useful for protocols that emit a small
+/// fixed header followed by a large, already-owned payload: pushing the
+/// header and the payload as two separate chunks avoids copying the payload
+/// at all.
+///
+/// [`Encoder`]: crate::codec::Encoder
+/// [`FramedWrite`]: crate::codec::FramedWrite
+/// [`FramedWrite::new_vectored`]: crate::codec::FramedWrite::new_vectored
+/// [`AsyncWrite::poll_write_vectored`]: tokio::io::AsyncWrite::poll_write_vectored
+pub trait EncoderVectored<Item> {
+    /// The type of encoding errors.
+    type Error: From<io::Error>;
+
+    /// Encodes a frame into the chunk queue, appending as many chunks as
+    /// needed.
+    fn encode_vectored(&mut self, item: Item, dst: &mut VecDeque<Bytes>) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,261 @@
+use crate::codec::decoder::Decoder;
+use crate::codec::framed::{Framed, FramedParts};
+use crate::codec::framed_impl::{FramedImpl, ReadFrame};
+use crate::codec::framed_write::Combine;
+
+use futures_core::Stream;
+use tokio::io::AsyncRead;
+
+use bytes::BytesMut;
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pin_project! {
+    /// A [`Stream`] of frames decoded from an `AsyncRead`.
+    ///
+    /// For examples of how to use `FramedRead` with a codec, see the
+    /// examples on the [`codec`] module.
+    ///
+    /// [`Stream`]: futures_core::Stream
+    /// [`codec`]: crate::codec
+    pub struct FramedRead<T, D> {
+        #[pin]
+        inner: FramedImpl<T, D, ReadFrame>,
+    }
+}
+
+impl<T, D> FramedRead<T, D>
+where
+    T: AsyncRead,
+{
+    /// Creates a new `FramedRead` with the given `decoder`.
+    pub fn new(inner: T, decoder: D) -> FramedRead<T, D> {
+        FramedRead {
+            inner: FramedImpl {
+                inner,
+                codec: decoder,
+                state: ReadFrame::default(),
+            },
+        }
+    }
+
+    /// Creates a new `FramedRead` with the given `decoder` and a buffer of
+    /// `capacity` initial size.
+    pub fn with_capacity(inner: T, decoder: D, capacity: usize) -> FramedRead<T, D> {
+        FramedRead {
+            inner: FramedImpl {
+                inner,
+                codec: decoder,
+                state: ReadFrame {
+                    buffer: BytesMut::with_capacity(capacity),
+                    ..Default::default()
+                },
+            },
+        }
+    }
+}
+
+impl<T, D> FramedRead<T, D> {
+    /// Returns a reference to the underlying I/O stream wrapped by
+    /// `FramedRead`.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// stream of data coming in as it may corrupt the stream of frames
+    /// otherwise being worked with.
+    pub fn get_ref(&self) -> &T {
+        &self.inner.inner
+    }
+
+    /// Returns a mutable reference to the underlying I/O stream wrapped by
+    /// `FramedRead`.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// stream of data coming in as it may corrupt the stream of frames
+    /// otherwise being worked with.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner.inner
+    }
+
+    /// Returns a pinned mutable reference to the underlying I/O stream
+    /// wrapped by `FramedRead`.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// stream of data coming in as it may corrupt the stream of frames
+    /// otherwise being worked with.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().inner.project().inner
+    }
+
+    /// Consumes the `FramedRead`, returning its underlying I/O stream.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// stream of data coming in as it may corrupt the stream of frames
+    /// otherwise being worked with.
+    pub fn into_inner(self) -> T {
+        self.inner.inner
+    }
+
+    /// Returns a reference to the underlying decoder.
+    pub fn decoder(&self) -> &D {
+        &self.inner.codec
+    }
+
+    /// Returns a mutable reference to the underlying decoder.
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.inner.codec
+    }
+
+    /// Maps the decoder `D` to `C`, preserving the read buffer
+    /// wrapped by `FramedRead`.
+    pub fn map_decoder<C, F>(self, map: F) -> FramedRead<T, C>
+    where
+        F: FnOnce(D) -> C,
+    {
+        // This could be potentially simplified once rust-lang/rust#86555 hits stable
+        let FramedImpl {
+            inner,
+            state,
+            codec,
+        } = self.inner;
+        FramedRead {
+            inner: FramedImpl {
+                inner,
+                state,
+                codec: map(codec),
+            },
+        }
+    }
+
+    /// Returns a mutable reference to the underlying decoder.
+    pub fn decoder_pin_mut(self: Pin<&mut Self>) -> &mut D {
+        self.project().inner.project().codec
+    }
+
+    /// Returns a reference to the read buffer.
+    pub fn read_buffer(&self) -> &BytesMut {
+        &self.inner.state.buffer
+    }
+
+    /// Returns a mutable reference to the read buffer.
+    pub fn read_buffer_mut(&mut self) -> &mut BytesMut {
+        &mut self.inner.state.buffer
+    }
+
+    /// Consumes the `FramedRead`, returning a full-duplex [`Framed`] that
+    /// writes with `encoder` and reads with the decoder already configured
+    /// on this `FramedRead`.
+    ///
+    /// The underlying I/O stream and the already-buffered, not-yet-decoded
+    /// bytes are moved into the returned `Framed` as-is, so nothing read so
+    /// far is lost. The `encoder` starts out with a fresh, empty write
+    /// buffer.
+    ///
+    /// This is the symmetric counterpart to [`FramedWrite::into_framed`]:
+    /// useful for protocols that start out in a read-only mode (e.g.
+    /// waiting on a handshake from the peer) and only need to start
+    /// encoding frames once something is ready to send back.
+    ///
+    /// [`Framed`]: crate::codec::Framed
+    /// [`FramedWrite::into_framed`]: crate::codec::FramedWrite::into_framed
+    pub fn into_framed<E>(self, encoder: E) -> Framed<T, Combine<E, D>> {
+        let FramedImpl {
+            inner,
+            state: ReadFrame { buffer, .. },
+            codec: decoder,
+        } = self.inner;
+
+        let mut parts = FramedParts::new(inner, Combine::new(encoder, decoder));
+        parts.read_buf = buffer;
+        Framed::from_parts(parts)
+    }
+}
+
+// This impl just defers to the underlying FramedImpl
+impl<T, D> Stream for FramedRead<T, D>
+where
+    T: AsyncRead,
+    D: Decoder,
+{
+    type Item = Result<D::Item, D::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next(cx)
+    }
+}
+
+// This impl just defers to the underlying T: Sink
+impl<T, I, D> Sink<I> for FramedRead<T, D>
+where
+    T: Sink<I>,
+{
+    type Error = T::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        self.project().inner.project().inner.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.project().inner.poll_close(cx)
+    }
+}
+
+impl<T, D> fmt::Debug for FramedRead<T, D>
+where
+    T: fmt::Debug,
+    D: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FramedRead")
+            .field("inner", &self.get_ref())
+            .field("decoder", &self.decoder())
+            .field("buffer", &self.inner.state.buffer)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    // A bare-bones `AsyncRead` good enough to satisfy `FramedRead`'s `T:
+    // AsyncRead` bound; this test never actually polls it.
+    #[derive(Default)]
+    struct MockReader;
+
+    impl AsyncRead for MockReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    // A placeholder decoder/encoder: `into_framed` doesn't require `Decoder`
+    // or `Encoder` bounds on its type parameters, so a bare marker type is
+    // enough to stand in for both halves here.
+    struct Noop;
+
+    #[test]
+    fn into_framed_preserves_read_buffer_and_resets_write_buffer() {
+        let mut framed_read = FramedRead::new(MockReader, Noop);
+        framed_read.read_buffer_mut().extend_from_slice(b"buffered");
+
+        let combined = framed_read.into_framed(Noop);
+        assert_eq!(&combined.read_buffer()[..], b"buffered");
+        assert!(combined.write_buffer().is_empty());
+    }
+}
@@ -1,17 +1,28 @@
+use crate::codec::decoder::Decoder;
 use crate::codec::encoder::Encoder;
+use crate::codec::encoder_vectored::EncoderVectored;
+use crate::codec::framed::{Framed, FramedParts};
 use crate::codec::framed_impl::{FramedImpl, WriteFrame};
 
 use futures_core::Stream;
 use tokio::io::AsyncWrite;
 
-use bytes::BytesMut;
+use bytes::{Buf, Bytes, BytesMut};
 use futures_sink::Sink;
 use pin_project_lite::pin_project;
+use std::collections::VecDeque;
 use std::fmt;
-use std::io;
+use std::io::{self, IoSlice};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+/// The maximum number of chunks passed to a single
+/// [`AsyncWrite::poll_write_vectored`] call made by a vectored
+/// `FramedWrite`.
+///
+/// [`AsyncWrite::poll_write_vectored`]: tokio::io::AsyncWrite::poll_write_vectored
+const MAX_VECTORED_CHUNKS: usize = 64;
+
 pin_project! {
     /// A [`Sink`] of frames encoded to an `AsyncWrite`.
     ///
@@ -30,6 +41,10 @@ pin_project! {
     pub struct FramedWrite<T, E> {
         #[pin]
         inner: FramedImpl<T, E, WriteFrame>,
+        // `Some` when constructed via `new_vectored`: frames are queued here
+        // as owned chunks and flushed with vectored writes instead of going
+        // through `inner`'s contiguous write buffer.
+        vectored: Option<VecDeque<Bytes>>,
     }
 }
 
@@ -45,6 +60,7 @@ where
                 codec: encoder,
                 state: WriteFrame::default(),
             },
+            vectored: None,
         }
     }
 
@@ -60,6 +76,32 @@ where
                     backpressure_boundary: capacity,
                 },
             },
+            vectored: None,
+        }
+    }
+
+    /// Creates a new `FramedWrite` with the given `encoder` that writes
+    /// frames through the vectored write path.
+    ///
+    /// Instead of encoding every frame into a single contiguous write
+    /// buffer, frames are queued as owned [`Bytes`] chunks and flushed with
+    /// [`AsyncWrite::poll_write_vectored`], which lets protocols that emit a
+    /// small header followed by a large, already-owned payload avoid a copy
+    /// of the payload. See [`EncoderVectored`] for the codec side of this.
+    ///
+    /// Falls back to one [`AsyncWrite::poll_write`] call per queued chunk
+    /// when the writer reports that it is not vectored.
+    ///
+    /// [`AsyncWrite::poll_write_vectored`]: tokio::io::AsyncWrite::poll_write_vectored
+    /// [`AsyncWrite::poll_write`]: tokio::io::AsyncWrite::poll_write
+    pub fn new_vectored(inner: T, encoder: E) -> FramedWrite<T, E> {
+        FramedWrite {
+            inner: FramedImpl {
+                inner,
+                codec: encoder,
+                state: WriteFrame::default(),
+            },
+            vectored: Some(VecDeque::new()),
         }
     }
 }
@@ -132,6 +174,7 @@ impl<T, E> FramedWrite<T, E> {
                 state,
                 codec: map(codec),
             },
+            vectored: self.vectored,
         }
     }
 
@@ -159,9 +202,197 @@ impl<T, E> FramedWrite<T, E> {
     pub fn set_backpressure_boundary(&mut self, boundary: usize) {
         self.inner.state.backpressure_boundary = boundary;
     }
+
+    /// Consumes the `FramedWrite`, returning its constituent parts.
+    ///
+    /// Unlike [`into_inner`], this preserves the encoder, the write buffer,
+    /// and the configured backpressure boundary, so a partially-encoded
+    /// frame still sitting in the buffer is not lost.
+    ///
+    /// If this `FramedWrite` was constructed with [`new_vectored`], any
+    /// chunks still queued for a vectored write are coalesced into
+    /// `write_buf`; the reconstructed `FramedWrite` returned by
+    /// [`from_parts`] always uses the contiguous write path.
+    ///
+    /// [`into_inner`]: Self::into_inner
+    /// [`new_vectored`]: Self::new_vectored
+    /// [`from_parts`]: Self::from_parts
+    pub fn into_parts(self) -> FramedWriteParts<T, E> {
+        let mut write_buf = self.inner.state.buffer;
+        if let Some(queue) = self.vectored {
+            for chunk in queue {
+                write_buf.extend_from_slice(&chunk);
+            }
+        }
+        FramedWriteParts {
+            io: self.inner.inner,
+            encoder: self.inner.codec,
+            write_buf,
+            backpressure_boundary: self.inner.state.backpressure_boundary,
+        }
+    }
+
+    /// Creates a new `FramedWrite` from existing parts, such as those
+    /// obtained from [`into_parts`].
+    ///
+    /// This is useful for swapping out the underlying transport (e.g.
+    /// upgrading a TCP stream to TLS) while preserving any partially-encoded
+    /// frame still queued for the writer.
+    ///
+    /// [`into_parts`]: Self::into_parts
+    pub fn from_parts(parts: FramedWriteParts<T, E>) -> FramedWrite<T, E> {
+        FramedWrite {
+            inner: FramedImpl {
+                inner: parts.io,
+                codec: parts.encoder,
+                state: WriteFrame {
+                    buffer: parts.write_buf,
+                    backpressure_boundary: parts.backpressure_boundary,
+                },
+            },
+            vectored: None,
+        }
+    }
+
+    /// Consumes the `FramedWrite`, returning a full-duplex [`Framed`] that
+    /// reads with `decoder` and writes with the encoder already configured on
+    /// this `FramedWrite`.
+    ///
+    /// The underlying I/O stream and the already-allocated write buffer are
+    /// moved into the returned `Framed` as-is, so any bytes that were encoded
+    /// but not yet flushed are not lost. The `decoder` starts out with a
+    /// fresh, empty read buffer.
+    ///
+    /// If this `FramedWrite` was constructed with [`new_vectored`], any
+    /// chunks still queued for a vectored write are coalesced into the
+    /// returned `Framed`'s write buffer.
+    ///
+    /// The configured [`backpressure_boundary`] also carries over to the
+    /// returned `Framed`.
+    ///
+    /// This is useful for protocols that start out in a write-only mode
+    /// (e.g. sending a handshake) and only need to start decoding frames
+    /// once the handshake response is expected.
+    ///
+    /// [`Framed`]: crate::codec::Framed
+    /// [`new_vectored`]: Self::new_vectored
+    /// [`backpressure_boundary`]: Self::backpressure_boundary
+    pub fn into_framed<D>(self, decoder: D) -> Framed<T, Combine<E, D>> {
+        let FramedImpl {
+            inner,
+            state: WriteFrame {
+                mut buffer,
+                backpressure_boundary,
+            },
+            codec: encoder,
+        } = self.inner;
+
+        if let Some(queue) = self.vectored {
+            for chunk in queue {
+                buffer.extend_from_slice(&chunk);
+            }
+        }
+
+        let mut parts = FramedParts::new(inner, Combine::new(encoder, decoder));
+        parts.write_buf = buffer;
+        let mut framed = Framed::from_parts(parts);
+        framed.set_backpressure_boundary(backpressure_boundary);
+        framed
+    }
+
+    /// Encodes `item` directly into the vectored write queue using
+    /// [`EncoderVectored::encode_vectored`], rather than the contiguous
+    /// [`Encoder::encode`] path used by [`Sink::start_send`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `FramedWrite` was not constructed with
+    /// [`new_vectored`]: a contiguous `FramedWrite` never drains the
+    /// vectored queue, so silently starting to fill it here would mean the
+    /// chunks sit there forever.
+    ///
+    /// [`Sink::start_send`]: futures_sink::Sink::start_send
+    /// [`new_vectored`]: Self::new_vectored
+    pub fn start_send_vectored<I>(self: Pin<&mut Self>, item: I) -> Result<(), E::Error>
+    where
+        E: EncoderVectored<I>,
+    {
+        let this = self.project();
+        let queue = this.vectored.as_mut().expect(
+            "start_send_vectored called on a FramedWrite not constructed with new_vectored",
+        );
+        let codec = this.inner.project().codec;
+        codec.encode_vectored(item, queue)
+    }
+}
+
+/// A structure holding the constituent parts of a [`FramedWrite`].
+///
+/// This is useful for swapping out the underlying I/O while preserving the
+/// encoder and any partially-encoded frame still sitting in the write
+/// buffer, which would otherwise be lost by going through
+/// [`FramedWrite::into_inner`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct FramedWriteParts<T, E> {
+    /// The underlying I/O stream.
+    pub io: T,
+
+    /// The encoder used to encode frames.
+    pub encoder: E,
+
+    /// The write buffer, which may still hold an encoded-but-unflushed
+    /// frame.
+    pub write_buf: BytesMut,
+
+    /// The configured backpressure boundary.
+    pub backpressure_boundary: usize,
+}
+
+/// A codec that splices together an encoder and a decoder so a
+/// [`FramedWrite`] can be promoted to a full-duplex [`Framed`] without
+/// re-encoding or losing any data already queued for the writer.
+///
+/// Produced by [`FramedWrite::into_framed`].
+#[derive(Debug)]
+pub struct Combine<E, D> {
+    encoder: E,
+    decoder: D,
+}
+
+impl<E, D> Combine<E, D> {
+    pub(crate) fn new(encoder: E, decoder: D) -> Self {
+        Combine { encoder, decoder }
+    }
+}
+
+impl<I, E, D> Encoder<I> for Combine<E, D>
+where
+    E: Encoder<I>,
+{
+    type Error = E::Error;
+
+    fn encode(&mut self, item: I, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encoder.encode(item, dst)
+    }
 }
 
-// This impl just defers to the underlying FramedImpl
+impl<E, D> Decoder for Combine<E, D>
+where
+    D: Decoder,
+{
+    type Item = D::Item;
+    type Error = D::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.decoder.decode(src)
+    }
+}
+
+// When `vectored` is `None` this impl just defers to the underlying
+// FramedImpl, exactly as before. When it is `Some` (i.e. this FramedWrite
+// was constructed via `new_vectored`), frames are queued as chunks and
+// flushed through `poll_flush_vectored` instead.
 impl<T, I, E> Sink<I> for FramedWrite<T, E>
 where
     T: AsyncWrite,
@@ -171,19 +402,123 @@ where
     type Error = E::Error;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().inner.poll_ready(cx)
+        let mut this = self.project();
+        if let Some(queue) = this.vectored.as_mut() {
+            if vectored_len(queue) < this.inner.state.backpressure_boundary {
+                return Poll::Ready(Ok(()));
+            }
+            let io = this.inner.as_mut().project().inner;
+            return poll_flush_vectored(io, cx, queue).map(|res| res.map_err(Self::Error::from));
+        }
+        this.inner.poll_ready(cx)
     }
 
     fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
-        self.project().inner.start_send(item)
+        let this = self.project();
+        if let Some(queue) = this.vectored {
+            let codec = this.inner.project().codec;
+            let mut buf = BytesMut::new();
+            codec.encode(item, &mut buf)?;
+            if !buf.is_empty() {
+                queue.push_back(buf.freeze());
+            }
+            return Ok(());
+        }
+        this.inner.start_send(item)
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().inner.poll_flush(cx)
+        let mut this = self.project();
+        if let Some(queue) = this.vectored.as_mut() {
+            let io = this.inner.as_mut().project().inner;
+            return poll_flush_vectored(io, cx, queue).map(|res| res.map_err(Self::Error::from));
+        }
+        this.inner.poll_flush(cx)
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().inner.poll_close(cx)
+        let mut this = self.project();
+        if let Some(queue) = this.vectored.as_mut() {
+            let mut io = this.inner.as_mut().project().inner;
+            match poll_flush_vectored(io.as_mut(), cx, queue) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e.into())),
+                Poll::Pending => return Poll::Pending,
+            }
+            return io.poll_shutdown(cx).map(|res| res.map_err(Self::Error::from));
+        }
+        this.inner.poll_close(cx)
+    }
+}
+
+/// Returns the total number of bytes still queued across every chunk.
+fn vectored_len(queue: &VecDeque<Bytes>) -> usize {
+    queue.iter().map(Bytes::len).sum()
+}
+
+/// Drains `queue` into `io`, writing up to [`MAX_VECTORED_CHUNKS`] chunks per
+/// vectored write and repeating until the queue is empty, or falling back to
+/// one contiguous write per chunk when `io` reports it is not vectored.
+fn poll_flush_vectored<T>(
+    mut io: Pin<&mut T>,
+    cx: &mut Context<'_>,
+    queue: &mut VecDeque<Bytes>,
+) -> Poll<io::Result<()>>
+where
+    T: AsyncWrite,
+{
+    loop {
+        // `EncoderVectored` impls are not required to skip empty chunks, so
+        // drop any before attempting a write: an empty write is otherwise
+        // indistinguishable from `WriteZero` on a well-behaved writer.
+        while matches!(queue.front(), Some(chunk) if chunk.is_empty()) {
+            queue.pop_front();
+        }
+        if queue.is_empty() {
+            break;
+        }
+
+        let n = if io.as_mut().is_write_vectored() {
+            let mut slices = [IoSlice::new(&[]); MAX_VECTORED_CHUNKS];
+            let len = queue
+                .iter()
+                .zip(slices.iter_mut())
+                .map(|(chunk, slot)| *slot = IoSlice::new(chunk))
+                .count();
+            match io.as_mut().poll_write_vectored(cx, &slices[..len]) {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        } else {
+            match io.as_mut().poll_write(cx, &queue[0]) {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        };
+
+        if n == 0 {
+            return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+        }
+        advance_queue(queue, n);
+    }
+
+    io.poll_flush(cx)
+}
+
+/// Advances past `n` written bytes, popping fully-drained chunks and
+/// truncating the partially-written front chunk.
+fn advance_queue(queue: &mut VecDeque<Bytes>, mut n: usize) {
+    while n > 0 {
+        let front_len = queue[0].len();
+        if n >= front_len {
+            n -= front_len;
+            queue.pop_front();
+        } else {
+            queue[0].advance(n);
+            n = 0;
+        }
     }
 }
 
@@ -212,3 +547,206 @@ where
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Waker;
+
+    // An `AsyncWrite` that records every `poll_write`/`poll_write_vectored`
+    // call it receives, so tests can assert on how many syscalls a flush
+    // would have made and whether it went through the vectored path.
+    #[derive(Default)]
+    struct MockWriter {
+        vectored: bool,
+        writes: Vec<Vec<u8>>,
+        vectored_calls: usize,
+        flushed: Vec<u8>,
+    }
+
+    impl AsyncWrite for MockWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            this.writes.push(buf.to_vec());
+            this.flushed.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            this.vectored_calls += 1;
+            let mut n = 0;
+            for buf in bufs {
+                this.flushed.extend_from_slice(buf);
+                n += buf.len();
+            }
+            Poll::Ready(Ok(n))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.vectored
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn noop_waker() -> Waker {
+        use std::task::{RawWaker, RawWakerVTable};
+
+        const VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    }
+
+    struct ChunkEncoder;
+
+    impl Encoder<Vec<u8>> for ChunkEncoder {
+        type Error = io::Error;
+
+        fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+            dst.extend_from_slice(&item);
+            Ok(())
+        }
+    }
+
+    impl EncoderVectored<Vec<u8>> for ChunkEncoder {
+        type Error = io::Error;
+
+        fn encode_vectored(
+            &mut self,
+            item: Vec<u8>,
+            dst: &mut VecDeque<Bytes>,
+        ) -> Result<(), Self::Error> {
+            dst.push_back(Bytes::from(item));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn into_parts_from_parts_round_trip() {
+        let writer = MockWriter::default();
+        let mut framed = FramedWrite::with_capacity(writer, ChunkEncoder, 64);
+        framed.write_buffer_mut().extend_from_slice(b"partial");
+
+        let parts = framed.into_parts();
+        assert_eq!(&parts.write_buf[..], b"partial");
+        assert_eq!(parts.backpressure_boundary, 64);
+
+        let framed = FramedWrite::from_parts(parts);
+        assert_eq!(&framed.write_buffer()[..], b"partial");
+        assert_eq!(framed.backpressure_boundary(), 64);
+    }
+
+    #[test]
+    fn into_parts_coalesces_vectored_queue() {
+        let writer = MockWriter::default();
+        let mut framed = FramedWrite::new_vectored(writer, ChunkEncoder);
+        {
+            let mut pinned = Pin::new(&mut framed);
+            pinned
+                .as_mut()
+                .start_send_vectored(b"hello".to_vec())
+                .unwrap();
+            pinned
+                .as_mut()
+                .start_send_vectored(b" world".to_vec())
+                .unwrap();
+        }
+
+        let parts = framed.into_parts();
+        assert_eq!(&parts.write_buf[..], b"hello world");
+    }
+
+    #[test]
+    fn into_framed_preserves_write_buffer_and_boundary() {
+        let writer = MockWriter::default();
+        let mut framed = FramedWrite::with_capacity(writer, ChunkEncoder, 128);
+        framed.write_buffer_mut().extend_from_slice(b"queued");
+        framed.set_backpressure_boundary(128);
+
+        let combined = framed.into_framed(ChunkEncoder);
+        assert_eq!(&combined.write_buffer()[..], b"queued");
+        assert_eq!(combined.backpressure_boundary(), 128);
+    }
+
+    #[test]
+    fn start_send_vectored_panics_without_new_vectored() {
+        let writer = MockWriter::default();
+        let mut framed = FramedWrite::new(writer, ChunkEncoder);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Pin::new(&mut framed)
+                .start_send_vectored(b"oops".to_vec())
+                .unwrap();
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn vectored_flush_coalesces_into_single_poll_write_vectored_call() {
+        let writer = MockWriter {
+            vectored: true,
+            ..Default::default()
+        };
+        let mut framed = FramedWrite::new_vectored(writer, ChunkEncoder);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        {
+            let mut pinned = Pin::new(&mut framed);
+            pinned
+                .as_mut()
+                .start_send_vectored(b"hello".to_vec())
+                .unwrap();
+            pinned
+                .as_mut()
+                .start_send_vectored(b" world".to_vec())
+                .unwrap();
+            let poll = pinned.poll_flush(&mut cx);
+            assert!(matches!(poll, Poll::Ready(Ok(()))));
+        }
+
+        assert_eq!(framed.get_ref().vectored_calls, 1);
+        assert_eq!(&framed.get_ref().flushed[..], b"hello world");
+    }
+
+    #[test]
+    fn vectored_flush_skips_empty_chunks_on_non_vectored_writer() {
+        let mut queue = VecDeque::new();
+        queue.push_back(Bytes::new());
+        queue.push_back(Bytes::from_static(b"data"));
+
+        let mut writer = MockWriter::default();
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let poll = poll_flush_vectored(Pin::new(&mut writer), &mut cx, &mut queue);
+        assert!(matches!(poll, Poll::Ready(Ok(()))));
+        assert_eq!(&writer.flushed[..], b"data");
+        // The empty leading chunk must not have produced an `Ok(0)` write
+        // that got misread as `WriteZero`.
+        assert_eq!(writer.writes, vec![b"data".to_vec()]);
+    }
+}
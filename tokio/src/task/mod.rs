@@ -0,0 +1,19 @@
+//! Asynchronous green-threads.
+//!
+//! ## What are Tasks?
+//!
+//! A _task_ is a light weight, non-blocking unit of execution. A task
+//! is similar to an OS thread, but rather than being managed by the OS
+//! scheduler, they are managed by the [Tokio runtime]. Another name for
+//! this general pattern is [green threads]. If you are familiar with
+//! Go, Tokio tasks are similar to goroutines.
+//!
+//! [Tokio runtime]: crate::runtime::Runtime
+//! [green threads]: https://en.wikipedia.org/wiki/Green_threads
+
+pub use crate::util::wake::{waker_from_arc, ArcWake};
+
+cfg_rt! {
+    pub use crate::util::wake::arc_wake_ref as waker_ref;
+    pub use crate::util::wake::WakerRef;
+}
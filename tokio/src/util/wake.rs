@@ -12,13 +12,61 @@ pub(crate) trait Wake: Send + Sync + Sized + 'static {
     fn wake_by_ref(arc_self: &Arc<Self>);
 }
 
+/// A way of waking up a specific task, expressed as an `Arc<Self>`.
+///
+/// This mirrors `futures_task::ArcWake` and exists so that users writing
+/// their own executors, `poll_fn` helpers, or test harnesses on top of
+/// Tokio can mint a [`Waker`] from an `Arc` without pulling in
+/// `futures-task` just for its `ArcWake`. It is built on top of the crate's
+/// own [`Wake`]/[`RawWakerVTable`] machinery via a blanket [`Wake`] impl, so
+/// [`waker_from_arc`] and the runtime-gated `arc_wake_ref` reuse that
+/// machinery verbatim.
+///
+/// [`RawWakerVTable`]: std::task::RawWakerVTable
+pub trait ArcWake: Send + Sync + Sized + 'static {
+    /// Wakes the task associated with this waker, consuming the `Arc`.
+    fn wake(self: Arc<Self>);
+
+    /// Wakes the task associated with this waker, without consuming the
+    /// `Arc`.
+    ///
+    /// The default implementation clones `self` and delegates to
+    /// [`wake`](ArcWake::wake); override it to avoid the clone when waking
+    /// can be done through a shared reference.
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        ArcWake::wake(arc_self.clone());
+    }
+}
+
+impl<T: ArcWake> Wake for T {
+    fn wake(arc_self: Arc<Self>) {
+        ArcWake::wake(arc_self);
+    }
+
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        ArcWake::wake_by_ref(arc_self);
+    }
+}
+
+/// Creates a [`Waker`] from an `Arc<impl ArcWake>`.
+///
+/// This reuses the same [`RawWakerVTable`] as the rest of the crate's
+/// internal executors, so the returned `Waker` is as cheap to clone as any
+/// other Tokio waker.
+pub fn waker_from_arc<W: ArcWake>(wake: Arc<W>) -> Waker {
+    self::waker(wake)
+}
+
 cfg_rt! {
     use std::marker::PhantomData;
     use std::ops::Deref;
 
     /// A `Waker` that is only valid for a given lifetime.
+    ///
+    /// Returned by [`arc_wake_ref`], the borrowed counterpart to
+    /// [`waker_from_arc`].
     #[derive(Debug)]
-    pub(crate) struct WakerRef<'a> {
+    pub struct WakerRef<'a> {
         waker: ManuallyDrop<Waker>,
         _p: PhantomData<&'a ()>,
     }
@@ -42,6 +90,14 @@ cfg_rt! {
             _p: PhantomData,
         }
     }
+
+    /// Creates a [`WakerRef`] from a reference to an `Arc<impl ArcWake>`,
+    /// without bumping the `Arc`'s strong count.
+    ///
+    /// This is the borrowed counterpart to [`waker_from_arc`].
+    pub fn arc_wake_ref<W: ArcWake>(wake: &Arc<W>) -> WakerRef<'_> {
+        self::waker_ref(wake)
+    }
 }
 
 /// Creates a waker from a `Arc<impl Wake>`.
@@ -83,3 +139,43 @@ unsafe fn wake_by_ref_arc_raw<T: Wake>(data: *const ()) {
 unsafe fn drop_arc_raw<T: Wake>(data: *const ()) {
     drop(Arc::<T>::from_raw(data.cast()));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct Flag(AtomicBool);
+
+    impl ArcWake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn waker_from_arc_wakes_task() {
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = waker_from_arc(flag.clone());
+        waker.wake();
+        assert!(flag.0.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn waker_from_arc_wake_by_ref_wakes_task() {
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = waker_from_arc(flag.clone());
+        waker.wake_by_ref();
+        assert!(flag.0.load(Ordering::SeqCst));
+    }
+
+    cfg_rt! {
+        #[test]
+        fn arc_wake_ref_wakes_task() {
+            let flag = Arc::new(Flag(AtomicBool::new(false)));
+            let waker_ref = arc_wake_ref(&flag);
+            waker_ref.wake_by_ref();
+            assert!(flag.0.load(Ordering::SeqCst));
+        }
+    }
+}